@@ -1,11 +1,14 @@
 use avian3d::prelude::*;
-use bevy::color::palettes::tailwind::{PINK_100, RED_500};
+use bevy::color::palettes::tailwind::{PINK_100, RED_500, YELLOW_400};
 use bevy::core_pipeline::Skybox;
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::core_pipeline::smaa::Smaa;
 use bevy::core_pipeline::tonemapping::{DebandDither, Tonemapping};
-use bevy::pbr::ScreenSpaceAmbientOcclusion;
+use bevy::pbr::{
+    ScreenSpaceAmbientOcclusion, ScreenSpaceAmbientOcclusionQualityLevel,
+};
 use bevy::picking::pointer::PointerInteraction;
+use bevy::platform::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy::scene::SceneInstanceReady;
 
@@ -20,11 +23,27 @@ impl Plugin for CornPlugin {
             bevy_skein::SkeinPlugin::default(),
             bevy_panorbit_camera::PanOrbitCameraPlugin,
         ))
+        .register_type::<AmbientSettings>()
+        .register_type::<BloomSettings>()
+        .register_type::<SsaoSettings>()
+        .register_type::<SkyboxSettings>()
+        .register_type::<LevelTransition>()
         .add_systems(
             Startup,
             (setup_camera_and_environment, setup_mesh_and_animation),
         )
-        .add_systems(Update, draw_mesh_intersections);
+        .add_systems(
+            Update,
+            (
+                draw_mesh_intersections,
+                drive_animation_transitions,
+                request_state_from_keys,
+                (sync_level_focus_to_camera_focus, drive_level_transitions).chain(),
+                drive_proximity_animation,
+                toggle_physics_debug_rendering,
+                highlight_selected_entity,
+            ),
+        );
 
         #[cfg(feature = "dev")]
         app.add_plugins((
@@ -32,6 +51,9 @@ impl Plugin for CornPlugin {
                 enable_multipass_for_primary_context: true,
             },
             bevy_inspector_egui::quick::WorldInspectorPlugin::new(),
+            bevy_inspector_egui::quick::FilterQueryInspectorPlugin::<
+                With<Selected>,
+            >::default(),
         ));
     }
 }
@@ -39,26 +61,70 @@ impl Plugin for CornPlugin {
 const FACTORY: &str = "factory.glb";
 const CORN: &str = "corn.glb";
 
-fn setup_mesh_and_animation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut graphs: ResMut<Assets<AnimationGraph>>,
-) {
-    // Create an animation graph containing a single animation. We want the "run"
-    // animation from our example asset, which has an index of two.
-    let (graph, index) = AnimationGraph::from_clip(
-        asset_server
-            .load(GltfAssetLabel::Animation(0).from_asset(FACTORY)),
-    );
+/// How long a crossfade between two animation states takes.
+const TRANSITION_SECONDS: f32 = 0.3;
+
+/// A named state in the animation state machine. Each variant maps to a clip
+/// node in the entity's [`AnimationGraph`] (see [`AnimationToPlay::nodes`]).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Run,
+}
+
+/// The animation clips that make up a state, keyed by [`AnimationState`] and
+/// paired with the clip's index in the glTF asset.
+const ANIMATION_STATES: [(AnimationState, usize); 3] = [
+    (AnimationState::Idle, 0),
+    (AnimationState::Walk, 1),
+    (AnimationState::Run, 2),
+];
+
+/// Builds the [`AnimationToPlay`] state machine for `scene_asset`: one root
+/// blend node plus one clip node per [`ANIMATION_STATES`] entry, so callers
+/// can crossfade between states by animating the blend weights rather than
+/// hard-cutting between clips. Assumes `scene_asset` lays its clips out the
+/// same way `factory.glb` does (idle/walk/run at indices 0/1/2).
+fn build_animation_to_play(
+    asset_server: &AssetServer,
+    graphs: &mut Assets<AnimationGraph>,
+    scene_asset: &str,
+) -> AnimationToPlay {
+    let mut graph = AnimationGraph::new();
+    let blend_node = graph.add_blend(1.0, graph.root);
+    let nodes: HashMap<AnimationState, AnimationNodeIndex> = ANIMATION_STATES
+        .iter()
+        .map(|(state, clip_index)| {
+            let clip_node = graph.add_clip(
+                asset_server.load(
+                    GltfAssetLabel::Animation(*clip_index).from_asset(scene_asset),
+                ),
+                if *state == AnimationState::Idle { 1.0 } else { 0.0 },
+                blend_node,
+            );
+            (*state, clip_node)
+        })
+        .collect();
 
     // Store the animation graph as an asset.
     let graph_handle = graphs.add(graph);
 
-    // Create a component that stores a reference to our animation.
-    let animation_to_play = AnimationToPlay {
+    AnimationToPlay {
         graph_handle,
-        index,
-    };
+        nodes,
+        current: AnimationState::Idle,
+        target: AnimationState::Idle,
+        transition: Timer::from_seconds(TRANSITION_SECONDS, TimerMode::Once),
+    }
+}
+
+fn setup_mesh_and_animation(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    let animation_to_play = build_animation_to_play(&asset_server, &mut graphs, FACTORY);
 
     // Start loading the asset as a scene and store a reference to it in a
     // SceneRoot component. This component will automatically spawn a scene
@@ -70,19 +136,49 @@ fn setup_mesh_and_animation(
 
     // Spawn an entity with our components, and connect it to an observer that
     // will trigger when the scene is loaded and spawned.
+    // Note: `ProximityAnimation` is deliberately not attached here. This
+    // entity is also driven by the keys 1/2/3 demo in
+    // `request_state_from_keys`, and having both request states on the same
+    // entity would mean proximity silently overrides every keypress each
+    // `Update`. See the dedicated proximity-demo factory below instead.
     commands
-        .spawn((animation_to_play, mesh_scene))
-        .observe(play_animation_when_ready);
+        .spawn((animation_to_play, mesh_scene, ActiveLevel))
+        .observe(play_animation_when_ready)
+        .observe(apply_scene_environment_settings)
+        .observe(prepare_level_transition_sensors);
 
-    commands.spawn((
-        SceneRoot(
-            asset_server
-                .load(GltfAssetLabel::Scene(0).from_asset(CORN)),
-        ),
-        Transform::from_xyz(5.0, 10.0, 5.0).with_rotation(
-            Quat::from_euler(EulerRot::XYZ, 3.57, 0.14, 2.95),
-        ),
-    ));
+    commands
+        .spawn((
+            SceneRoot(
+                asset_server
+                    .load(GltfAssetLabel::Scene(0).from_asset(CORN)),
+            ),
+            Transform::from_xyz(5.0, 10.0, 5.0).with_rotation(
+                Quat::from_euler(EulerRot::XYZ, 3.57, 0.14, 2.95),
+            ),
+        ))
+        .observe(apply_scene_environment_settings);
+
+    // A second factory instance, off to the side, whose animation state is
+    // driven purely by distance from the camera focus rather than the keys
+    // 1/2/3 demo — this is what actually exercises `ProximityAnimation`.
+    commands
+        .spawn((
+            build_animation_to_play(&asset_server, &mut graphs, FACTORY),
+            SceneRoot(
+                asset_server
+                    .load(GltfAssetLabel::Scene(0).from_asset(FACTORY)),
+            ),
+            Transform::from_xyz(-8.0, 0.0, -8.0),
+            ProximityAnimation {
+                trigger_radius: 8.0,
+                near_state: AnimationState::Run,
+                far_state: AnimationState::Idle,
+            },
+        ))
+        .observe(play_animation_when_ready)
+        .observe(apply_scene_environment_settings)
+        .observe(prepare_level_transition_sensors);
 }
 
 fn setup_camera_and_environment(
@@ -126,6 +222,256 @@ fn setup_camera_and_environment(
             ..default()
         },
     ));
+
+    // A separate proxy for the level-transition focus, kept at
+    // `PanOrbitCamera::focus` (not the camera eye) by
+    // `sync_level_focus_to_camera_focus`. It's a plain (non-sensor)
+    // kinematic collider: avian3d doesn't report contacts between two
+    // sensors, and `LevelTransition` volumes are sensors, so this side of
+    // the pair has to be solid for `CollisionStarted` to fire.
+    commands.spawn((
+        LevelFocus,
+        Transform::from_translation(INITIAL_FOCUS),
+        RigidBody::Kinematic,
+        Collider::sphere(0.3),
+        CollisionEventsEnabled,
+    ));
+}
+
+/// Scene-level ambient light overrides, exported from Blender as a marker
+/// component on the scene root or any of its descendants.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+struct AmbientSettings {
+    color: Color,
+    brightness: f32,
+}
+
+/// Scene-level bloom overrides, exported from Blender.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+struct BloomSettings {
+    intensity: f32,
+}
+
+/// Scene-level SSAO quality, exported from Blender. `quality` is a
+/// `ScreenSpaceAmbientOcclusionQualityLevel` ordinal (0 = Low, 1 = Medium,
+/// 2 = High, 3 = Ultra), since `bevy_skein` reflects onto plain field types
+/// rather than Bevy's own enum.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+struct SsaoSettings {
+    quality: u8,
+}
+
+/// Scene-level skybox/environment-map overrides, exported from Blender.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+struct SkyboxSettings {
+    brightness: f32,
+}
+
+/// Walks the hierarchy spawned for a glTF scene once it's ready, looking for
+/// the Blender-authored marker components above, and patches the camera's
+/// lighting/post-processing to match. Lets artists tune this from Blender
+/// instead of the hardcoded defaults in `setup_camera_and_environment`.
+fn apply_scene_environment_settings(
+    trigger: Trigger<SceneInstanceReady>,
+    children: Query<&Children>,
+    ambient_settings: Query<&AmbientSettings>,
+    bloom_settings: Query<&BloomSettings>,
+    ssao_settings: Query<&SsaoSettings>,
+    skybox_settings: Query<&SkyboxSettings>,
+    mut ambient_light: ResMut<AmbientLight>,
+    mut camera: Query<
+        (
+            &mut Bloom,
+            &mut ScreenSpaceAmbientOcclusion,
+            &mut Skybox,
+            &mut EnvironmentMapLight,
+        ),
+        With<Camera3d>,
+    >,
+) {
+    let Ok((mut bloom, mut ssao, mut skybox, mut environment_map)) =
+        camera.single_mut()
+    else {
+        return;
+    };
+
+    let scene_root = trigger.target();
+    for entity in
+        std::iter::once(scene_root).chain(children.iter_descendants(scene_root))
+    {
+        if let Ok(settings) = ambient_settings.get(entity) {
+            ambient_light.color = settings.color;
+            ambient_light.brightness = settings.brightness;
+        }
+        if let Ok(settings) = bloom_settings.get(entity) {
+            bloom.intensity = settings.intensity;
+        }
+        if let Ok(settings) = ssao_settings.get(entity) {
+            ssao.quality_level = match settings.quality {
+                0 => ScreenSpaceAmbientOcclusionQualityLevel::Low,
+                1 => ScreenSpaceAmbientOcclusionQualityLevel::Medium,
+                2 => ScreenSpaceAmbientOcclusionQualityLevel::High,
+                _ => ScreenSpaceAmbientOcclusionQualityLevel::Ultra,
+            };
+        }
+        if let Ok(settings) = skybox_settings.get(entity) {
+            skybox.brightness = settings.brightness;
+            environment_map.intensity = settings.brightness;
+        }
+    }
+}
+
+/// Marks the entity [`drive_level_transitions`] uses as the viewer's position for
+/// level-transition overlap checks: a proxy kept at the orbit camera's
+/// `focus` point, not the camera's own (eye) transform.
+#[derive(Component)]
+struct LevelFocus;
+
+/// Keeps the [`LevelFocus`] proxy's `Transform` at the active
+/// `PanOrbitCamera`'s `focus`, each `Update`, so level-transition overlap
+/// checks track where the viewer is looking rather than where the camera
+/// eye sits.
+fn sync_level_focus_to_camera_focus(
+    camera: Query<&bevy_panorbit_camera::PanOrbitCamera>,
+    mut focus: Query<&mut Transform, With<LevelFocus>>,
+) {
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+    let Ok(mut transform) = focus.single_mut() else {
+        return;
+    };
+    transform.translation = camera.focus;
+}
+
+/// Marks the `SceneRoot` hierarchy [`drive_level_transitions`] despawns when a
+/// transition fires. Only the explorable level carries this, not static
+/// decoration like the `corn.glb` prop.
+#[derive(Component)]
+struct ActiveLevel;
+
+/// A sensor trigger volume, exported from Blender onto a node placed in the
+/// level, that swaps the loaded scene for `target_scene` when the
+/// [`LevelFocus`] entity enters it.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct LevelTransition {
+    pub target_scene: String,
+}
+
+/// Once a scene is ready, give every [`LevelTransition`] node in its
+/// hierarchy a collider to sense overlaps with. Transitions may live several
+/// levels deep in the glTF hierarchy (under an empty, below the object that
+/// carries the mesh), so we scan all descendants of the scene root rather than
+/// only its direct children. The sensor collider goes on the marker node itself.
+fn prepare_level_transition_sensors(
+    trigger: Trigger<SceneInstanceReady>,
+    children: Query<&Children>,
+    transitions: Query<Entity, (With<LevelTransition>, Without<Collider>)>,
+    mut commands: Commands,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        if transitions.contains(entity) {
+            commands.entity(entity).insert((
+                Collider::cuboid(1.0, 1.0, 1.0),
+                Sensor,
+                CollisionEventsEnabled,
+            ));
+        }
+    }
+}
+
+/// Loads `target_scene` and spawns it the same way `setup_mesh_and_animation`
+/// spawns the initial level, re-using the same observer wiring (animation,
+/// environment settings, transition sensors) so a swapped-in scene behaves
+/// identically to the one loaded at startup.
+fn spawn_level_scene(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    graphs: &mut Assets<AnimationGraph>,
+    target_scene: &str,
+) {
+    commands
+        .spawn((
+            build_animation_to_play(asset_server, graphs, target_scene),
+            SceneRoot(
+                asset_server
+                    .load(GltfAssetLabel::Scene(0).from_asset(target_scene)),
+            ),
+            ActiveLevel,
+        ))
+        .observe(play_animation_when_ready)
+        .observe(apply_scene_environment_settings)
+        .observe(prepare_level_transition_sensors);
+}
+
+/// Watches for the [`LevelFocus`] entity overlapping a [`LevelTransition`]
+/// sensor and, when it does, despawns the current [`ActiveLevel`] hierarchy
+/// and loads the transition's target scene in its place. `overlapping`
+/// guards against re-triggering every frame the focus entity is still inside
+/// the same sensor.
+fn drive_level_transitions(
+    focus: Query<Entity, With<LevelFocus>>,
+    active_levels: Query<Entity, With<ActiveLevel>>,
+    transitions: Query<(Entity, &LevelTransition)>,
+    children: Query<&Children>,
+    mut collision_started: EventReader<CollisionStarted>,
+    mut collision_ended: EventReader<CollisionEnded>,
+    mut overlapping: Local<HashSet<Entity>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    let Ok(focus_entity) = focus.single() else {
+        return;
+    };
+
+    let other_of = |a: Entity, b: Entity| -> Option<Entity> {
+        if a == focus_entity {
+            Some(b)
+        } else if b == focus_entity {
+            Some(a)
+        } else {
+            None
+        }
+    };
+
+    for &CollisionEnded(a, b) in collision_ended.read() {
+        if let Some(other) = other_of(a, b) {
+            overlapping.remove(&other);
+        }
+    }
+
+    for &CollisionStarted(a, b) in collision_started.read() {
+        let Some(other) = other_of(a, b) else {
+            continue;
+        };
+        if !overlapping.insert(other) {
+            continue;
+        }
+
+        let transition = transitions.iter().find(|(transition_entity, _)| {
+            *transition_entity == other
+                || children.iter_descendants(*transition_entity).any(|d| d == other)
+        });
+        let Some((_, transition)) = transition else {
+            continue;
+        };
+
+        for level in &active_levels {
+            commands.entity(level).despawn();
+        }
+        spawn_level_scene(
+            &mut commands,
+            &asset_server,
+            &mut graphs,
+            &transition.target_scene,
+        );
+    }
 }
 
 fn play_animation_when_ready(
@@ -137,21 +483,18 @@ fn play_animation_when_ready(
 ) {
     // The entity we spawned in `setup_mesh_and_animation` is the trigger's target.
     // Start by finding the AnimationToPlay component we added to that entity.
-    if let Ok(animation_to_play) =
-        animations_to_play.get(trigger.target())
-    {
+    if let Ok(animation_to_play) = animations_to_play.get(trigger.target()) {
         // The SceneRoot component will have spawned the scene as a hierarchy
         // of entities parented to our entity. Since the asset contained a skinned
         // mesh and animations, it will also have spawned an animation player
         // component. Search our entity's descendants to find the animation player.
         for child in children.iter_descendants(trigger.target()) {
             if let Ok(mut player) = players.get_mut(child) {
-                // Tell the animation player to start the animation and keep
-                // repeating it.
-                //
-                // If you want to try stopping and switching animations, see the
-                // `animated_mesh_control.rs` example.
-                player.play(animation_to_play.index).repeat();
+                // Every clip node stays active so the graph's blend weights
+                // (rather than `AnimationPlayer::play`) decide what's audible.
+                for node in animation_to_play.nodes.values() {
+                    player.play(*node).repeat();
+                }
 
                 // Add the animation graph. This only needs to be done once to
                 // connect the animation player to the mesh.
@@ -163,10 +506,163 @@ fn play_animation_when_ready(
     }
 }
 
+/// Requests that the given entity's animation state machine transition to
+/// `state`, crossfading over [`TRANSITION_SECONDS`]. A no-op if `entity`
+/// has no [`AnimationToPlay`] or is already targeting `state`.
+pub fn request_state(
+    animations: &mut Query<&mut AnimationToPlay>,
+    entity: Entity,
+    state: AnimationState,
+) {
+    if let Ok(mut animation_to_play) = animations.get_mut(entity) {
+        if animation_to_play.target == state {
+            return;
+        }
+        animation_to_play.target = state;
+        animation_to_play
+            .transition
+            .set_duration(std::time::Duration::from_secs_f32(TRANSITION_SECONDS));
+        animation_to_play.transition.reset();
+    }
+}
+
+/// Drives the crossfade weight between `current` and `target` for every
+/// animated entity, each `Update`, by writing into its [`AnimationGraph`].
+fn drive_animation_transitions(
+    time: Res<Time>,
+    mut animations: Query<&mut AnimationToPlay>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    for mut animation_to_play in &mut animations {
+        // Once a transition lands, `current == target` and there is nothing
+        // left to drive — checking this regardless of the timer's finished
+        // state matters at startup, where `current` and `target` already
+        // agree but the freshly-constructed `Once` timer hasn't fired yet.
+        if animation_to_play.current == animation_to_play.target {
+            continue;
+        }
+
+        animation_to_play.transition.tick(time.delta());
+        let w = animation_to_play.transition.fraction();
+
+        let Some(graph) = graphs.get_mut(&animation_to_play.graph_handle) else {
+            continue;
+        };
+        let current = animation_to_play.current;
+        let target = animation_to_play.target;
+        // Drive every node, not just `current`/`target`: a `request_state`
+        // mid-fade only overwrites `target`, so a state that was fading in
+        // before the interrupt (and is now neither `current` nor `target`)
+        // would otherwise be left stuck at its last partial weight forever.
+        for (state, node) in &animation_to_play.nodes {
+            graph[*node].weight = if *state == current {
+                1.0 - w
+            } else if *state == target {
+                w
+            } else {
+                0.0
+            };
+        }
+
+        if animation_to_play.transition.finished() {
+            animation_to_play.current = animation_to_play.target;
+        }
+    }
+}
+
+/// Demo input: keys 1/2/3 request the idle/walk/run states on every animated
+/// entity that is not driven by [`ProximityAnimation`].
+fn request_state_from_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut animations: Query<&mut AnimationToPlay>,
+    entities: Query<Entity, (With<AnimationToPlay>, Without<ProximityAnimation>)>,
+) {
+    let state = if keys.just_pressed(KeyCode::Digit1) {
+        AnimationState::Idle
+    } else if keys.just_pressed(KeyCode::Digit2) {
+        AnimationState::Walk
+    } else if keys.just_pressed(KeyCode::Digit3) {
+        AnimationState::Run
+    } else {
+        return;
+    };
+
+    for entity in &entities {
+        request_state(&mut animations, entity, state);
+    }
+}
+
+/// Ties an entity's animation to its distance from the pan-orbit camera's
+/// focus point: `near_state` plays inside `trigger_radius`, `far_state`
+/// outside it. Useful for LOD-style animation, e.g. only running factory
+/// machinery once the viewer zooms in on it.
+#[derive(Component, Debug, Clone)]
+pub struct ProximityAnimation {
+    pub trigger_radius: f32,
+    pub near_state: AnimationState,
+    pub far_state: AnimationState,
+}
+
+/// Drives [`ProximityAnimation`], each `Update`, from the distance between an
+/// entity's `Transform` and the active `PanOrbitCamera`'s focus. Reuses the
+/// state-machine transitions from [`request_state`] where an entity has an
+/// [`AnimationToPlay`]; otherwise falls back to pausing/resuming whatever
+/// clips are already playing on its `AnimationPlayer` directly.
+fn drive_proximity_animation(
+    camera: Query<&bevy_panorbit_camera::PanOrbitCamera>,
+    proximity: Query<(Entity, &Transform, &ProximityAnimation)>,
+    mut animations: Query<&mut AnimationToPlay>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    let Ok(camera) = camera.single() else {
+        return;
+    };
+
+    for (entity, transform, proximity_animation) in &proximity {
+        let distance = transform.translation.distance(camera.focus);
+        let desired = if distance <= proximity_animation.trigger_radius {
+            proximity_animation.near_state
+        } else {
+            proximity_animation.far_state
+        };
+
+        if animations.contains(entity) {
+            request_state(&mut animations, entity, desired);
+        } else if let Ok(mut player) = players.get_mut(entity) {
+            // No `AnimationToPlay` graph to pull a node index from, so the
+            // best we can do without one is drive whatever's already been
+            // started on this player: pause it when far and resume it at a
+            // state-derived speed when near. `AnimationPlayer::stop` would
+            // drop the node from the active set entirely, and we have no
+            // stored node index to `play` it back from — so once stopped it
+            // could never be resumed.
+            for (_, active_animation) in player.playing_animations_mut() {
+                match desired {
+                    AnimationState::Idle => {
+                        active_animation.pause();
+                    }
+                    AnimationState::Walk | AnimationState::Run => {
+                        let speed = if desired == AnimationState::Run {
+                            1.0
+                        } else {
+                            0.5
+                        };
+                        active_animation.resume().set_speed(speed);
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// A system that draws hit indicators for every pointer.
 fn draw_mesh_intersections(
     q_pointers: Query<&PointerInteraction>,
     mut gizmos: Gizmos,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut commands: Commands,
+    previously_selected: Query<Entity, With<Selected>>,
+    transforms: Query<(&Transform, Option<&Collider>)>,
 ) {
     for (point, normal) in q_pointers
         .iter()
@@ -180,13 +676,64 @@ fn draw_mesh_intersections(
             PINK_100,
         );
     }
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        if let Some(&(entity, _)) = q_pointers
+            .iter()
+            .filter_map(|interaction| interaction.get_nearest_hit())
+            .next()
+        {
+            for previous in &previously_selected {
+                commands.entity(previous).remove::<Selected>();
+            }
+            commands.entity(entity).insert(Selected);
+
+            if let Ok((transform, collider)) = transforms.get(entity) {
+                info!(
+                    "selected {entity:?}: transform = {transform:?}, collider = {collider:?}"
+                );
+            }
+        }
+    }
+}
+
+/// Marks the entity most recently picked via [`draw_mesh_intersections`]'s
+/// click handling. Only one entity carries this at a time.
+#[derive(Component)]
+struct Selected;
+
+/// Highlights the current [`Selected`] entity each `Update` so the click in
+/// [`draw_mesh_intersections`] has a visible, persistent result.
+fn highlight_selected_entity(
+    selected: Query<&GlobalTransform, With<Selected>>,
+    mut gizmos: Gizmos,
+) {
+    for transform in &selected {
+        gizmos.sphere(transform.translation(), 0.3, YELLOW_400);
+    }
+}
+
+/// Toggles avian3d's debug gizmos (colliders, contacts, etc.) on F3 by
+/// flipping the `PhysicsGizmos` config group rather than removing
+/// `PhysicsDebugPlugin`, so the toggle takes effect immediately at runtime.
+fn toggle_physics_debug_rendering(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut gizmo_config_store: ResMut<GizmoConfigStore>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        let config = gizmo_config_store.config_mut::<PhysicsGizmos>().0;
+        config.enabled = !config.enabled;
+    }
 }
 
-// A component that stores a reference to an animation we want to play. This is
-// created when we start loading the mesh (see `setup_mesh_and_animation`) and
-// read when the mesh has spawned (see `play_animation_once_loaded`).
+/// A component that stores an animation state machine: the graph built by
+/// [`build_animation_to_play`], the clip node for each [`AnimationState`], and
+/// the `current`/`target` states driven by [`drive_animation_transitions`].
 #[derive(Component)]
-struct AnimationToPlay {
+pub struct AnimationToPlay {
     graph_handle: Handle<AnimationGraph>,
-    index: AnimationNodeIndex,
+    nodes: HashMap<AnimationState, AnimationNodeIndex>,
+    current: AnimationState,
+    target: AnimationState,
+    transition: Timer,
 }